@@ -1,8 +1,183 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
 use arboard::Clipboard;
 use clap::{Parser, ValueEnum};
 use emojis::Emoji;
 use vizia::prelude::*;
 
+const USAGE_FILE_NAME: &str = "usage.json";
+const RECENTLY_USED_LIMIT: usize = 30;
+const ITEMS_PER_ROW: usize = 10;
+
+/// Search terms that should also pull in emoji whose names don't literally
+/// contain the word, mirroring Discourse's `SEARCH_ALIASES` table.
+const SEARCH_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "sad",
+        &["frowning_face", "slightly_frowning_face", "sob", "cry"],
+    ),
+    ("cry", &["sob"]),
+    ("happy", &["smile", "smiley", "grinning", "blush"]),
+    ("angry", &["rage", "pout", "triumph"]),
+    ("laugh", &["joy", "laughing", "rofl"]),
+    ("love", &["heart", "heart_eyes", "kissing_heart"]),
+];
+
+fn search_aliases(term: &str) -> Option<&'static [&'static str]> {
+    SEARCH_ALIASES
+        .iter()
+        .find(|(key, _)| *key == term)
+        .map(|(_, shortcodes)| *shortcodes)
+}
+
+/// Score given to an emoji whose shortcode was pulled in through [`SEARCH_ALIASES`]
+/// rather than a direct name match.
+const ALIAS_MATCH_SCORE: u32 = 400;
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack_chars = haystack.chars();
+    needle.chars().all(|c| haystack_chars.any(|h| h == c))
+}
+
+/// Ranks a candidate emoji name against a search query: exact match scores
+/// highest, then a matching word, then a prefix, then a loose subsequence.
+/// Within a tier, shorter names score higher. Only the name is scored, not
+/// the shortcodes.
+fn search_score(query: &str, name: &str) -> u32 {
+    let mut score = 0;
+
+    if name == query {
+        score = 1000;
+    } else if name.split(['_', ' ']).any(|word| word == query) {
+        score = 800;
+    } else if name.starts_with(query) || name.split(['_', ' ']).any(|word| word.starts_with(query))
+    {
+        score = 600;
+    } else if is_subsequence(query, name) {
+        score = 200;
+    }
+
+    if score > 0 {
+        score += (50usize.saturating_sub(name.len())) as u32;
+    }
+
+    score
+}
+
+fn filtered_emojis(
+    group: &Group,
+    search: &str,
+    usage: &HashMap<String, u32>,
+) -> Vec<&'static Emoji> {
+    if *group == Group::RecentlyUsed {
+        let mut counted: Vec<(&String, &u32)> = usage.iter().collect();
+        counted.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        let recently_used = counted
+            .into_iter()
+            .take(RECENTLY_USED_LIMIT)
+            .filter_map(|(unicode, _)| emojis::get(unicode));
+
+        if search.is_empty() {
+            recently_used.collect()
+        } else if let Some(code) = search.strip_prefix(':') {
+            let code = code.trim_end_matches(':');
+
+            recently_used
+                .filter(|e| e.shortcodes().any(|shortcode| shortcode.contains(code)))
+                .collect()
+        } else if search.chars().count() <= 1 {
+            Vec::new()
+        } else {
+            let alias_shortcodes = search_aliases(search);
+
+            let mut scored: Vec<(u32, &'static Emoji)> = recently_used
+                .filter_map(|e| {
+                    let mut score = search_score(search, e.name());
+
+                    if alias_shortcodes
+                        .is_some_and(|shortcodes| e.shortcodes().any(|s| shortcodes.contains(&s)))
+                    {
+                        score = score.max(ALIAS_MATCH_SCORE);
+                    }
+
+                    (score > 0).then_some((score, e))
+                })
+                .collect();
+
+            scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+            scored.into_iter().map(|(_, e)| e).collect()
+        }
+    } else if search.is_empty() {
+        emojis::iter()
+            .filter(|e| {
+                e.unicode_version() < emojis::UnicodeVersion::new(15, 0)
+                    && Some(e.group()) == group_to_emoji_group(group)
+            })
+            .collect()
+    } else if let Some(code) = search.strip_prefix(':') {
+        let code = code.trim_end_matches(':');
+
+        emojis::iter()
+            .filter(|e| {
+                e.unicode_version() < emojis::UnicodeVersion::new(15, 0)
+                    && e.shortcodes().any(|shortcode| shortcode.contains(code))
+            })
+            .collect()
+    } else if search.chars().count() <= 1 {
+        Vec::new()
+    } else {
+        let alias_shortcodes = search_aliases(search);
+
+        let mut scored: Vec<(u32, &'static Emoji)> = emojis::iter()
+            .filter(|e| e.unicode_version() < emojis::UnicodeVersion::new(15, 0))
+            .filter_map(|e| {
+                let mut score = search_score(search, e.name());
+
+                if alias_shortcodes
+                    .is_some_and(|shortcodes| e.shortcodes().any(|s| shortcodes.contains(&s)))
+                {
+                    score = score.max(ALIAS_MATCH_SCORE);
+                }
+
+                (score > 0).then_some((score, e))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+        scored.into_iter().map(|(_, e)| e).collect()
+    }
+}
+
+fn usage_file_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("emoji-picker").join(USAGE_FILE_NAME))
+}
+
+fn load_usage_counter() -> HashMap<String, u32> {
+    usage_file_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_usage_counter(usage: &HashMap<String, u32>) {
+    let Some(path) = usage_file_path() else {
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    if let Ok(json) = serde_json::to_string(usage) {
+        let _ = fs::write(path, json);
+    }
+}
+
 #[derive(Clone, Debug, ValueEnum)]
 enum Theme {
     System,
@@ -10,6 +185,13 @@ enum Theme {
     Dark,
 }
 
+#[derive(Clone, Debug, ValueEnum)]
+enum Format {
+    Unicode,
+    Shortcode,
+    HtmlEntity,
+}
+
 /// Emoji Picker
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -17,10 +199,15 @@ struct Args {
     // /// theme to use, light or dark, if not specified, system theme will be used
     #[arg(short, long, default_value = "system")]
     theme: Theme,
+
+    // /// output format used when copying an emoji to the clipboard
+    #[arg(short, long, default_value = "unicode")]
+    format: Format,
 }
 
 #[derive(Clone, Data, PartialEq)]
 pub enum Group {
+    RecentlyUsed,
     SmileysAndEmotion,
     PeopleAndBody,
     AnimalsAndNature,
@@ -32,17 +219,18 @@ pub enum Group {
     Flags,
 }
 
-fn group_to_emoji_group(group: &Group) -> emojis::Group {
+fn group_to_emoji_group(group: &Group) -> Option<emojis::Group> {
     match group {
-        Group::SmileysAndEmotion => emojis::Group::SmileysAndEmotion,
-        Group::PeopleAndBody => emojis::Group::PeopleAndBody,
-        Group::AnimalsAndNature => emojis::Group::AnimalsAndNature,
-        Group::FoodAndDrink => emojis::Group::FoodAndDrink,
-        Group::TravelAndPlaces => emojis::Group::TravelAndPlaces,
-        Group::Activities => emojis::Group::Activities,
-        Group::Objects => emojis::Group::Objects,
-        Group::Symbols => emojis::Group::Symbols,
-        Group::Flags => emojis::Group::Flags,
+        Group::RecentlyUsed => None,
+        Group::SmileysAndEmotion => Some(emojis::Group::SmileysAndEmotion),
+        Group::PeopleAndBody => Some(emojis::Group::PeopleAndBody),
+        Group::AnimalsAndNature => Some(emojis::Group::AnimalsAndNature),
+        Group::FoodAndDrink => Some(emojis::Group::FoodAndDrink),
+        Group::TravelAndPlaces => Some(emojis::Group::TravelAndPlaces),
+        Group::Activities => Some(emojis::Group::Activities),
+        Group::Objects => Some(emojis::Group::Objects),
+        Group::Symbols => Some(emojis::Group::Symbols),
+        Group::Flags => Some(emojis::Group::Flags),
     }
 }
 
@@ -67,13 +255,46 @@ fn skin_tone_to_emoji_skin_tone(skin_tone: &SkinTone) -> emojis::SkinTone {
     }
 }
 
+#[derive(Clone, Data, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
 #[derive(Lens)]
 pub struct AppData {
     search: String,
     group: Group,
     skin_tone: SkinTone,
-    filter: (Group, SkinTone, String),
+    filter: (Group, SkinTone, String, usize),
     clipboard: Clipboard,
+    usage: HashMap<String, u32>,
+    selected_index: usize,
+    format: Format,
+}
+
+impl AppData {
+    fn copy_to_clipboard(&mut self, emoji: &Emoji) {
+        let text = match self.format {
+            Format::Unicode => emoji.as_str().to_string(),
+            Format::Shortcode => match emoji.shortcode() {
+                Some(shortcode) => format!(":{}:", shortcode),
+                None => emoji.as_str().to_string(),
+            },
+            Format::HtmlEntity => emoji
+                .as_str()
+                .chars()
+                .map(|c| format!("&#x{:x};", c as u32))
+                .collect(),
+        };
+
+        self.clipboard.set_text(text).unwrap();
+
+        *self.usage.entry(emoji.as_str().to_string()).or_insert(0) += 1;
+        save_usage_counter(&self.usage);
+    }
 }
 
 pub enum AppEvent<'e> {
@@ -81,6 +302,8 @@ pub enum AppEvent<'e> {
     Group(Group),
     SkinTone(SkinTone),
     Clipboard(&'e Emoji),
+    MoveSelection(Direction),
+    CopySelected,
 }
 
 impl Model for AppData {
@@ -88,22 +311,81 @@ impl Model for AppData {
         event.map(|app_event, _meta| match app_event {
             AppEvent::Search(search) => {
                 self.search = search.to_string();
+                self.selected_index = 0;
                 self.filter = (
                     self.group.clone(),
                     self.skin_tone.clone(),
                     search.to_string(),
+                    self.selected_index,
                 );
             }
             AppEvent::Group(group) => {
                 self.group = group.clone();
-                self.filter = (group.clone(), self.skin_tone.clone(), self.search.clone());
+                self.selected_index = 0;
+                self.filter = (
+                    group.clone(),
+                    self.skin_tone.clone(),
+                    self.search.clone(),
+                    self.selected_index,
+                );
             }
             AppEvent::SkinTone(skin_tone) => {
                 self.skin_tone = skin_tone.clone();
-                self.filter = (self.group.clone(), skin_tone.clone(), self.search.clone());
+                self.filter = (
+                    self.group.clone(),
+                    skin_tone.clone(),
+                    self.search.clone(),
+                    self.selected_index,
+                );
             }
             AppEvent::Clipboard(emoji) => {
-                self.clipboard.set_text(emoji.as_str()).unwrap();
+                self.copy_to_clipboard(emoji);
+            }
+            AppEvent::MoveSelection(direction) => {
+                let emojis = filtered_emojis(&self.group, &self.search, &self.usage);
+
+                if emojis.is_empty() {
+                    return;
+                }
+
+                let last_index = emojis.len() - 1;
+                let row = self.selected_index / ITEMS_PER_ROW;
+                let row_count = last_index / ITEMS_PER_ROW + 1;
+
+                self.selected_index = match direction {
+                    Direction::Left => self.selected_index.saturating_sub(1),
+                    Direction::Right => (self.selected_index + 1).min(last_index),
+                    Direction::Up => {
+                        if row == 0 {
+                            self.selected_index
+                        } else {
+                            self.selected_index - ITEMS_PER_ROW
+                        }
+                    }
+                    Direction::Down => {
+                        if row + 1 >= row_count {
+                            self.selected_index
+                        } else {
+                            (self.selected_index + ITEMS_PER_ROW).min(last_index)
+                        }
+                    }
+                };
+
+                self.filter = (
+                    self.group.clone(),
+                    self.skin_tone.clone(),
+                    self.search.clone(),
+                    self.selected_index,
+                );
+            }
+            AppEvent::CopySelected => {
+                let emoji = filtered_emojis(&self.group, &self.search, &self.usage)
+                    .get(self.selected_index)
+                    .copied();
+
+                if let Some(emoji) = emoji {
+                    self.copy_to_clipboard(emoji);
+                }
             }
         });
     }
@@ -136,8 +418,16 @@ fn main() {
             search: String::new(),
             group: Group::SmileysAndEmotion,
             skin_tone: SkinTone::Default,
-            filter: (Group::SmileysAndEmotion, SkinTone::Default, String::new()),
+            filter: (
+                Group::SmileysAndEmotion,
+                SkinTone::Default,
+                String::new(),
+                0,
+            ),
             clipboard: Clipboard::new().unwrap(),
+            usage: load_usage_counter(),
+            selected_index: 0,
+            format: args.format.clone(),
         }
         .build(cx);
 
@@ -146,6 +436,14 @@ fn main() {
                 Textbox::new(cx, AppData::search)
                     .placeholder("Search")
                     .on_edit(|cx, search| cx.emit(AppEvent::Search(search)))
+                    .on_key_down(|cx, event| match event.code {
+                        Code::ArrowUp => cx.emit(AppEvent::MoveSelection(Direction::Up)),
+                        Code::ArrowDown => cx.emit(AppEvent::MoveSelection(Direction::Down)),
+                        Code::ArrowLeft => cx.emit(AppEvent::MoveSelection(Direction::Left)),
+                        Code::ArrowRight => cx.emit(AppEvent::MoveSelection(Direction::Right)),
+                        Code::Enter => cx.emit(AppEvent::CopySelected),
+                        _ => {}
+                    })
                     .width(Stretch(1.0));
 
                 Dropdown::new(
@@ -203,6 +501,7 @@ fn main() {
                         Label::new(
                             cx,
                             match group {
+                                Group::RecentlyUsed => "Frequently Used",
                                 Group::SmileysAndEmotion => "Smileys & Emotion",
                                 Group::PeopleAndBody => "People & Body",
                                 Group::AnimalsAndNature => "Animals & Nature",
@@ -218,6 +517,7 @@ fn main() {
                     .width(Stretch(1.0))
                 }
 
+                group_button(cx, Group::RecentlyUsed, "🕒");
                 group_button(cx, Group::SmileysAndEmotion, "😊");
                 group_button(cx, Group::PeopleAndBody, "👨‍👩‍👧‍👦");
                 group_button(cx, Group::AnimalsAndNature, "🐶🌳");
@@ -233,23 +533,12 @@ fn main() {
 
             Binding::new(cx, AppData::filter, |cx, data| {
                 ScrollView::new(cx, 0.0, 0.0, false, true, move |cx| {
-                    let (group, skin_tone, search) = data.get(cx);
-                    let iter = emojis::iter().filter(|e| {
-                        let unicode_version =
-                            e.unicode_version() < emojis::UnicodeVersion::new(15, 0);
+                    let (group, skin_tone, search, selected_index) = data.get(cx);
+                    let usage = AppData::usage.get(cx);
 
-                        if search.is_empty() {
-                            unicode_version && e.group() == group_to_emoji_group(&group)
-                        } else {
-                            unicode_version
-                                && e.name().contains(&search)
-                                && search.chars().count() > 1
-                        }
-                    });
-
-                    let items_per_row = 10;
+                    let emojis = filtered_emojis(&group, &search, &usage);
 
-                    let emojis: Vec<&emojis::Emoji> = iter.collect();
+                    let items_per_row = ITEMS_PER_ROW;
 
                     let mut row = emojis.len() / items_per_row;
 
@@ -276,7 +565,7 @@ fn main() {
                                     None => emoji,
                                 };
 
-                                Button::new(
+                                let button = Button::new(
                                     cx,
                                     |cx| cx.emit(AppEvent::Clipboard(emoji)),
                                     |cx| {
@@ -285,10 +574,20 @@ fn main() {
                                     },
                                 )
                                 .tooltip(|cx| {
-                                    Label::new(cx, emoji.name());
+                                    let label = match emoji.shortcode() {
+                                        Some(shortcode) => {
+                                            format!("{} :{}:", emoji.name(), shortcode)
+                                        }
+                                        None => emoji.name().to_string(),
+                                    };
+                                    Label::new(cx, &label);
                                 })
                                 .width(Stretch(1.0))
                                 .height(Pixels(48.0));
+
+                                if index == selected_index {
+                                    button.background_color(Color::rgba(0, 0, 0, 10));
+                                }
                             }
                         })
                         .width(Stretch(1.0))
@@ -306,3 +605,20 @@ fn main() {
     .resizable(false)
     .run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_alias_shortcodes_exist() {
+        for (term, shortcodes) in SEARCH_ALIASES {
+            for shortcode in *shortcodes {
+                assert!(
+                    emojis::iter().any(|e| e.shortcodes().any(|s| s == *shortcode)),
+                    "alias {term:?} -> {shortcode:?} does not match any emoji shortcode"
+                );
+            }
+        }
+    }
+}